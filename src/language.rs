@@ -8,7 +8,7 @@ macro_rules! language_enum {
     ([$($language:ident),*]) => {
         /// Enumerates all language supported
         #[allow(non_camel_case_types)]
-        #[derive(Copy,Clone,Debug)]
+        #[derive(Copy,Clone,Debug,PartialEq,Eq,Hash)]
         pub enum Language {
             $( $language, )*
         }
@@ -28,18 +28,7 @@ language_enum!([DE, EN, ES, FR, IT, JA, KO, PT_PT, PT_BR]);
 impl FromStr for Language {
     type Err = failure::Error;
     fn from_str(it: &str) -> Result<Language, Self::Err> {
-        match &*it.to_lowercase() {
-            "de" => Ok(Language::DE),
-            "en" => Ok(Language::EN),
-            "es" => Ok(Language::ES),
-            "fr" => Ok(Language::FR),
-            "it" => Ok(Language::IT),
-            "ja" => Ok(Language::JA),
-            "ko" => Ok(Language::KO),
-            "pt_pt" => Ok(Language::PT_PT),
-            "pt_br" => Ok(Language::PT_BR),
-            _ => Err(format_err!("Unknown language {}", it)),
-        }
+        it.parse::<Locale>().map(|locale| locale.language)
     }
 }
 
@@ -68,3 +57,197 @@ impl Language {
         SPACE
     }
 }
+
+/// A parsed BCP-47 locale tag, e.g. `en`, `pt-BR` or `en-Latn-US`.
+///
+/// Unlike [`Language`], a `Locale` retains the script and region subtags
+/// instead of discarding them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub language: Language,
+    pub script: Option<String>,
+    pub region: Option<String>,
+}
+
+impl FromStr for Locale {
+    type Err = failure::Error;
+    fn from_str(tag: &str) -> Result<Locale, Self::Err> {
+        let subtags: Vec<&str> = tag.split(['-', '_']).collect();
+        let language_subtag = subtags
+            .first()
+            .filter(|subtag| !subtag.is_empty())
+            .ok_or_else(|| format_err!("Invalid locale tag {}", tag))?;
+
+        let mut script = None;
+        let mut region = None;
+
+        for subtag in &subtags[1..] {
+            if script.is_none() && region.is_none() && is_script_subtag(subtag) {
+                script = Some(title_case_subtag(subtag));
+            } else if region.is_none() && is_region_subtag(subtag) {
+                region = Some(subtag.to_uppercase());
+            } else {
+                return Err(format_err!(
+                    "Unexpected subtag '{}' in locale tag {}",
+                    subtag,
+                    tag
+                ));
+            }
+        }
+
+        let language = language_from_subtag(language_subtag, region.as_deref())?;
+
+        Ok(Locale {
+            language,
+            script,
+            region,
+        })
+    }
+}
+
+fn is_script_subtag(subtag: &str) -> bool {
+    subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_region_subtag(subtag: &str) -> bool {
+    (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+        || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn title_case_subtag(subtag: &str) -> String {
+    let mut chars = subtag.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn language_from_subtag(
+    language_subtag: &str,
+    region: Option<&str>,
+) -> Result<Language, failure::Error> {
+    if language_subtag.len() < 2
+        || language_subtag.len() > 3
+        || !language_subtag.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return Err(format_err!("Invalid language subtag {}", language_subtag));
+    }
+    match &*language_subtag.to_lowercase() {
+        "de" => Ok(Language::DE),
+        "en" => Ok(Language::EN),
+        "es" => Ok(Language::ES),
+        "fr" => Ok(Language::FR),
+        "it" => Ok(Language::IT),
+        "ja" => Ok(Language::JA),
+        "ko" => Ok(Language::KO),
+        "pt" => match region {
+            Some(r) if r.eq_ignore_ascii_case("BR") => Ok(Language::PT_BR),
+            _ => Ok(Language::PT_PT),
+        },
+        other => Err(format_err!("Unknown language {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_from_str_accepts_legacy_codes() {
+        // Given
+        let tags = ["en", "DE", "pt_pt", "pt_br"];
+
+        // When
+        let languages: Vec<Language> = tags.iter().map(|tag| tag.parse().unwrap()).collect();
+
+        // Then
+        assert_eq!("en", languages[0].to_string());
+        assert_eq!("de", languages[1].to_string());
+        assert_eq!("pt_pt", languages[2].to_string());
+        assert_eq!("pt_br", languages[3].to_string());
+    }
+
+    #[test]
+    fn language_from_str_accepts_bcp47_tags() {
+        // Given
+        let tags = ["en-US", "pt-BR", "pt-PT", "pt", "en-Latn-US", "de-AT"];
+
+        // When
+        let languages: Result<Vec<Language>, _> =
+            tags.iter().map(|tag| tag.parse::<Language>()).collect();
+
+        // Then
+        assert!(languages.is_ok());
+    }
+
+    #[test]
+    fn language_from_str_rejects_unknown_language_subtag() {
+        // Given
+        let tag = "zh-Hans-CN";
+
+        // When
+        let result = tag.parse::<Language>();
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn language_from_str_discards_script_and_region() {
+        // Given
+        let tags = ["en-US", "pt-BR", "pt-PT", "pt", "de-AT"];
+
+        // When
+        let languages: Vec<Language> = tags.iter().map(|tag| tag.parse().unwrap()).collect();
+
+        // Then
+        assert_eq!("en", languages[0].to_string());
+        assert_eq!("pt_br", languages[1].to_string());
+        assert_eq!("pt_pt", languages[2].to_string());
+        assert_eq!("pt_pt", languages[3].to_string());
+        assert_eq!("de", languages[4].to_string());
+    }
+
+    #[test]
+    fn locale_from_str_retains_script_and_region() {
+        // Given
+        let tag = "en-Latn-US";
+
+        // When
+        let locale: Locale = tag.parse().unwrap();
+
+        // Then
+        assert_eq!(Language::EN.to_string(), locale.language.to_string());
+        assert_eq!(Some("Latn".to_string()), locale.script);
+        assert_eq!(Some("US".to_string()), locale.region);
+    }
+
+    #[test]
+    fn locale_from_str_parses_language_script_and_region() {
+        // Given
+        let tag = "pt-BR";
+
+        // When
+        let locale: Locale = tag.parse().unwrap();
+
+        // Then
+        assert_eq!(Language::PT_BR.to_string(), locale.language.to_string());
+        assert_eq!(None, locale.script);
+        assert_eq!(Some("BR".to_string()), locale.region);
+    }
+
+    #[test]
+    fn locale_from_str_rejects_unknown_language() {
+        // Given
+        let tag = "xx-US";
+
+        // When
+        let result = tag.parse::<Locale>();
+
+        // Then
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod language;
+pub mod string;
+pub mod token;
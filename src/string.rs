@@ -3,6 +3,8 @@ use std::hash::Hasher;
 use std::ops::Range;
 use unicode_normalization::char::{compose, decompose_canonical, is_combining_mark};
 
+use crate::language::Language;
+
 const FNV_DEFAULT_KEY: u64 = 0xcbf2_9ce4_8422_2325;
 
 pub fn convert_to_char_range(string: &str, range: &Range<usize>) -> Range<usize> {
@@ -19,6 +21,20 @@ pub fn convert_to_byte_range(string: &str, range: &Range<usize>) -> Range<usize>
     }
 }
 
+pub fn convert_to_utf16_range(string: &str, range: &Range<usize>) -> Range<usize> {
+    Range {
+        start: convert_to_utf16_index(string, range.start),
+        end: convert_to_utf16_index(string, range.end),
+    }
+}
+
+pub fn convert_to_char_range_from_utf16(string: &str, range: &Range<usize>) -> Range<usize> {
+    Range {
+        start: convert_to_char_index_from_utf16(string, range.start),
+        end: convert_to_char_index_from_utf16(string, range.end),
+    }
+}
+
 pub fn convert_to_char_index(string: &str, byte_index: usize) -> usize {
     if string.is_empty() {
         return 0;
@@ -46,6 +62,61 @@ pub fn convert_to_byte_index(string: &str, char_index: usize) -> usize {
     result
 }
 
+/// Convert a char index into the matching UTF-16 code unit index, counting
+/// astral-plane chars (surrogate pairs) as two code units.
+pub fn convert_to_utf16_index(string: &str, char_index: usize) -> usize {
+    let mut result = 0;
+    for (current_char_index, char) in string.chars().enumerate() {
+        if current_char_index == char_index {
+            return result;
+        }
+        result += char.len_utf16()
+    }
+    result
+}
+
+/// Convert a UTF-16 code unit index into the matching char index. An index
+/// landing in the middle of a surrogate pair rounds up to the next char
+/// boundary rather than panicking.
+pub fn convert_to_char_index_from_utf16(string: &str, utf16_index: usize) -> usize {
+    if string.is_empty() {
+        return 0;
+    }
+    let mut acc = 0;
+    let mut last_char_index = 0;
+    for (char_index, char) in string.chars().enumerate() {
+        if utf16_index <= acc {
+            return char_index;
+        }
+        acc += char.len_utf16();
+        last_char_index = char_index;
+    }
+    last_char_index + 1
+}
+
+/// Translate a char range in a string normalized with `normalize_with_mapping`
+/// back into the matching char range in the original string.
+///
+/// # Examples
+///
+/// ```
+/// use snips_nlu_utils::string::{map_char_range, normalize_with_mapping};
+///
+/// let (normalized, mapping) = normalize_with_mapping("  HelöÀ ");
+/// assert_eq!("heloa", normalized);
+/// assert_eq!(2..7, map_char_range(&mapping, &(0..5)));
+/// ```
+pub fn map_char_range(mapping: &[usize], range: &Range<usize>) -> Range<usize> {
+    if range.start >= range.end {
+        let index = mapping.get(range.start).copied().unwrap_or(0);
+        return index..index;
+    }
+    Range {
+        start: mapping[range.start],
+        end: mapping[range.end - 1] + 1,
+    }
+}
+
 pub fn substring_with_char_range(string: String, range: &Range<usize>) -> String {
     string
         .chars()
@@ -65,7 +136,7 @@ pub fn suffix_from_char_index(string: String, index: usize) -> String {
 
 /// Apply the following normalization successively:
 /// 1) trim
-/// 2) remove diacritics
+/// 2) remove diacritics, or fold to ASCII when `fold_to_ascii` is `true`
 /// 3) lowercase
 ///
 /// # Examples
@@ -73,10 +144,16 @@ pub fn suffix_from_char_index(string: String, index: usize) -> String {
 /// ```
 /// use snips_nlu_utils::string::normalize;
 ///
-/// assert_eq!("heloa".to_string(), normalize("  HelöÀ "));
+/// assert_eq!("heloa".to_string(), normalize("  HelöÀ ", false));
+/// assert_eq!("strasse".to_string(), normalize("straße", true));
 /// ```
-pub fn normalize(string: &str) -> String {
-    remove_diacritics(string.trim()).to_lowercase()
+pub fn normalize(string: &str, fold_to_ascii: bool) -> String {
+    let trimmed = string.trim();
+    if fold_to_ascii {
+        ascii_fold(trimmed).to_lowercase()
+    } else {
+        remove_diacritics(trimmed).to_lowercase()
+    }
 }
 
 /// Remove common accentuations and diacritics
@@ -94,6 +171,127 @@ pub fn remove_diacritics(string: &str) -> String {
     string.chars().flat_map(remove_combination_marks).collect()
 }
 
+/// Single-char to ASCII replacements for the common Latin special letters
+/// that `remove_diacritics` cannot handle, either because they have no
+/// canonical decomposition (`ß`, `æ`, `œ`, `ø`, `ł`, `đ`, `þ`, `ð`) or
+/// because their decomposition alone does not match the expected ASCII
+/// transliteration (e.g. German `ü` is folded to `"ue"`, not `"u"`).
+const ASCII_FOLD_TABLE: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('æ', "ae"),
+    ('Æ', "AE"),
+    ('œ', "oe"),
+    ('Œ', "OE"),
+    ('ø', "o"),
+    ('Ø', "O"),
+    ('ł', "l"),
+    ('Ł', "L"),
+    ('đ', "d"),
+    ('Đ', "D"),
+    ('ð', "d"),
+    ('Ð', "D"),
+    ('þ', "th"),
+    ('Þ', "Th"),
+    ('ü', "ue"),
+    ('Ü', "Ue"),
+    ('ö', "oe"),
+    ('Ö', "Oe"),
+    ('ä', "ae"),
+    ('Ä', "Ae"),
+];
+
+/// Aggressively fold a string to ASCII, for matching against ASCII
+/// gazetteers.
+///
+/// Latin special letters that `remove_diacritics` leaves untouched or
+/// under-folds are replaced using `ASCII_FOLD_TABLE`; every other char
+/// falls back to the usual combination-mark removal. Non-Latin scripts are
+/// not transliterated: Korean syllables come out unchanged, while Japanese
+/// kana lose their voiced sound marks just as with `remove_diacritics`
+/// (e.g. `が` becomes `か`).
+///
+/// The table follows German convention for `ä`/`ö`/`ü` (`"ae"`/`"oe"`/`"ue"`).
+/// There is no locale parameter, so this will over-expand those letters for
+/// other Latin languages that use them with different transliteration
+/// conventions (e.g. Finnish or Swedish `ä`/`ö`, which are usually kept as
+/// plain `a`/`o` rather than expanded).
+///
+/// # Examples
+///
+/// ```
+/// use snips_nlu_utils::string::ascii_fold;
+///
+/// assert_eq!("strasse", ascii_fold("straße"));
+/// assert_eq!("muenchen", ascii_fold("münchen"));
+/// assert_eq!("안녕", ascii_fold("안녕"));
+/// ```
+pub fn ascii_fold(string: &str) -> String {
+    let mut result = String::new();
+    for character in string.chars() {
+        if let Some(&(_, folded)) = ASCII_FOLD_TABLE.iter().find(|(from, _)| *from == character) {
+            result.push_str(folded);
+        } else if let Some(plain) = remove_combination_marks(character) {
+            result.push(plain);
+        }
+    }
+    result
+}
+
+/// Apply the same normalization as `normalize(string, false)`, additionally
+/// returning a mapping from each char of the normalized output to the char
+/// index it came from in the original `string`.
+///
+/// Chars dropped by diacritics removal (e.g. a stray combining mark) have
+/// no corresponding entry, and the leading whitespace removed by `trim` is
+/// accounted for in the returned indices. Lowercasing is applied to the
+/// whole diacritics-stripped string at once (not char by char), so
+/// context-sensitive casing such as the Greek final sigma (`Σ` -> `ς`
+/// rather than `σ`) matches `normalize` exactly.
+///
+/// # Examples
+///
+/// ```
+/// use snips_nlu_utils::string::normalize_with_mapping;
+///
+/// let (normalized, mapping) = normalize_with_mapping("  HelöÀ ");
+/// assert_eq!("heloa", normalized);
+/// assert_eq!(vec![2, 3, 4, 5, 6], mapping);
+/// ```
+pub fn normalize_with_mapping(string: &str) -> (String, Vec<usize>) {
+    let trimmed = string.trim();
+    let leading_offset = string.chars().count() - string.trim_start().chars().count();
+
+    let mut folded = String::new();
+    let mut folded_mapping = Vec::new();
+
+    for (char_index, character) in trimmed.chars().enumerate() {
+        let original_index = leading_offset + char_index;
+        if let Some(kept) = remove_combination_marks(character) {
+            folded.push(kept);
+            folded_mapping.push(original_index);
+        }
+    }
+
+    let normalized = folded.to_lowercase();
+
+    // `char::to_lowercase` agrees with `str::to_lowercase` on how many
+    // chars each input char expands to (only the context-sensitive Greek
+    // final sigma differs, and only in *which* char it produces, not the
+    // count), so it can be used to split the whole-string-lowered output
+    // back into per-original-char spans.
+    let mut mapping = Vec::with_capacity(normalized.chars().count());
+    let mut normalized_chars = normalized.chars();
+    for (folded_char, original_index) in folded.chars().zip(folded_mapping) {
+        for _ in 0..folded_char.to_lowercase().count() {
+            if normalized_chars.next().is_some() {
+                mapping.push(original_index);
+            }
+        }
+    }
+
+    (normalized, mapping)
+}
+
 fn remove_combination_marks(character: char) -> Option<char> {
     let mut decomposition = Vec::<char>::new();
     decompose_canonical(character, |c| {
@@ -151,6 +349,46 @@ fn is_title_case(string: &str) -> bool {
     !first
 }
 
+/// Get a detailed, per-character shape of the string, using `x` for
+/// lowercase, `X` for uppercase, `d` for digits, `-` for punctuation and
+/// `o` for everything else, with runs of the same symbol collapsed.
+///
+/// Diacritics are ignored, so `"Héllo"` and `"Hello"` yield the same shape.
+///
+/// # Examples
+///
+/// ```
+/// use snips_nlu_utils::string::get_detailed_shape;
+///
+/// assert_eq!("Xx-Xxd", get_detailed_shape("Hello-World99"));
+/// ```
+pub fn get_detailed_shape(string: &str) -> String {
+    let punctuation = Language::EN.punctuation();
+    let mut shape = String::new();
+    let mut last_symbol: Option<char> = None;
+
+    for character in remove_diacritics(string).chars() {
+        let symbol = if character.is_lowercase() {
+            'x'
+        } else if character.is_uppercase() {
+            'X'
+        } else if character.is_ascii_digit() {
+            'd'
+        } else if punctuation.contains(character) {
+            '-'
+        } else {
+            'o'
+        };
+
+        if last_symbol != Some(symbol) {
+            shape.push(symbol);
+            last_symbol = Some(symbol);
+        }
+    }
+
+    shape
+}
+
 /// utility function for hashing str to i32 values
 pub fn hash_str_to_i32(input: &str) -> i32 {
     let mut hasher = FnvHasher::with_key(FNV_DEFAULT_KEY);
@@ -200,6 +438,34 @@ mod tests {
         assert_eq!("ö !!", &suffix);
     }
 
+    #[test]
+    fn convert_to_utf16_index_handles_astral_chars() {
+        // Given
+        let text = "a😀b";
+
+        // When/Then
+        assert_eq!(0, convert_to_utf16_index(text, 0));
+        assert_eq!(1, convert_to_utf16_index(text, 1));
+        assert_eq!(3, convert_to_utf16_index(text, 2));
+    }
+
+    #[test]
+    fn convert_to_char_index_from_utf16_handles_astral_chars() {
+        // Given
+        let text = "a😀b";
+
+        // When/Then
+        assert_eq!(0, convert_to_char_index_from_utf16(text, 0));
+        assert_eq!(1, convert_to_char_index_from_utf16(text, 1));
+        assert_eq!(2, convert_to_char_index_from_utf16(text, 2));
+        assert_eq!(2, convert_to_char_index_from_utf16(text, 3));
+    }
+
+    #[test]
+    fn convert_to_char_index_from_utf16_handles_empty_string() {
+        assert_eq!(0, convert_to_char_index_from_utf16("", 0));
+    }
+
     #[test]
     fn remove_combination_marks_works() {
         assert_eq!(Some('c'.to_owned()), remove_combination_marks('ç'));
@@ -215,7 +481,65 @@ mod tests {
 
     #[test]
     fn normalize_works() {
-        assert_eq!("heloa".to_string(), normalize("  HelöÀ "));
+        assert_eq!("heloa".to_string(), normalize("  HelöÀ ", false));
+    }
+
+    #[test]
+    fn normalize_with_ascii_fold_works() {
+        assert_eq!("strasse".to_string(), normalize("Straße", true));
+        assert_eq!("muenchen".to_string(), normalize("München", true));
+    }
+
+    #[test]
+    fn ascii_fold_works() {
+        assert_eq!("".to_owned(), ascii_fold(""));
+        assert_eq!("strasse", ascii_fold("straße"));
+        assert_eq!("ae", ascii_fold("æ"));
+        assert_eq!("oe", ascii_fold("œ"));
+        assert_eq!("o", ascii_fold("ø"));
+        assert_eq!("l", ascii_fold("ł"));
+        assert_eq!("d", ascii_fold("đ"));
+        assert_eq!("muenchen", ascii_fold("münchen"));
+        assert_eq!("ceaA", ascii_fold("çéaÀ"));
+        assert_eq!("안녕", ascii_fold("안녕"));
+    }
+
+    #[test]
+    fn normalize_with_mapping_works() {
+        // Given
+        let text = "  HelöÀ ";
+
+        // When
+        let (normalized, mapping) = normalize_with_mapping(text);
+
+        // Then
+        assert_eq!("heloa".to_string(), normalized);
+        assert_eq!(vec![2, 3, 4, 5, 6], mapping);
+    }
+
+    #[test]
+    fn normalize_with_mapping_matches_normalize_for_context_sensitive_casing() {
+        // Given
+        let text = "ΟΔΥΣΣΕΥΣ";
+
+        // When
+        let (normalized, mapping) = normalize_with_mapping(text);
+
+        // Then
+        assert_eq!(normalize(text, false), normalized);
+        assert_eq!(normalized.chars().count(), mapping.len());
+    }
+
+    #[test]
+    fn map_char_range_works() {
+        // Given
+        let (_, mapping) = normalize_with_mapping("  HelöÀ ");
+
+        // When
+        let original_range = map_char_range(&mapping, &(2..4));
+
+        // Then
+        assert_eq!(4..6, original_range);
     }
 
     #[test]
@@ -226,4 +550,11 @@ mod tests {
         assert_eq!("XXX", get_shape("HELLO"));
         assert_eq!("xX", get_shape("hEllo"));
     }
+
+    #[test]
+    fn detailed_shape_works() {
+        assert_eq!("Xx-Xxd", get_detailed_shape("Hello-World99"));
+        assert_eq!("Xx-Xxd", get_detailed_shape("Héllo-World99"));
+        assert_eq!("xo", get_detailed_shape("hi测"));
+    }
 }
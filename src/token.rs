@@ -0,0 +1,178 @@
+use std::ops::Range;
+
+use crate::language::Language;
+
+/// A single token produced by `tokenize`, with both its char and byte
+/// range in the original string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub value: String,
+    pub char_range: Range<usize>,
+    pub byte_range: Range<usize>,
+}
+
+/// Split `string` into `Token`s for `language`.
+///
+/// Splits on whitespace and emits each punctuation char (from
+/// `Language::punctuation`) as its own token. `Language::JA` and
+/// `Language::KO` are only sporadically space-delimited, so for these
+/// languages contiguous runs of CJK-script chars (Han, Hiragana, Katakana,
+/// Hangul) are additionally split one char per token instead of being
+/// grouped into a single run; embedded latin words and digit runs are
+/// still tokenized normally.
+///
+/// # Examples
+///
+/// ```
+/// use snips_nlu_utils::language::Language;
+/// use snips_nlu_utils::token::tokenize;
+///
+/// let tokens = tokenize("Hello, world!", Language::EN);
+/// let values: Vec<&str> = tokens.iter().map(|token| token.value.as_str()).collect();
+/// assert_eq!(vec!["Hello", ",", "world", "!"], values);
+/// ```
+pub fn tokenize(string: &str, language: Language) -> Vec<Token> {
+    let punctuation = language.punctuation();
+    let language_uses_cjk_script = matches!(language, Language::JA | Language::KO);
+
+    let mut tokens = Vec::new();
+    let mut word_start: Option<(usize, usize)> = None;
+    let mut char_index = 0;
+
+    for (byte_index, character) in string.char_indices() {
+        let is_own_token = character.is_whitespace() || punctuation.contains(character);
+        let split_this_char = language_uses_cjk_script && is_cjk_char(character);
+        if is_own_token || split_this_char {
+            if let Some(start) = word_start.take() {
+                tokens.push(build_token(string, start, (char_index, byte_index)));
+            }
+            if !character.is_whitespace() {
+                let end = (char_index + 1, byte_index + character.len_utf8());
+                tokens.push(build_token(string, (char_index, byte_index), end));
+            }
+        } else if word_start.is_none() {
+            word_start = Some((char_index, byte_index));
+        }
+        char_index += 1;
+    }
+    if let Some(start) = word_start.take() {
+        tokens.push(build_token(string, start, (char_index, string.len())));
+    }
+
+    tokens
+}
+
+/// Whether `character` belongs to a CJK script (Han, Hiragana, Katakana or
+/// Hangul), as opposed to latin letters, digits or other scripts that may
+/// be embedded in otherwise CJK-script text.
+fn is_cjk_char(character: char) -> bool {
+    matches!(character as u32,
+        0x1100..=0x11FF    // Hangul Jamo
+        | 0x3040..=0x30FF  // Hiragana, Katakana
+        | 0x3130..=0x318F  // Hangul compatibility Jamo
+        | 0x31F0..=0x31FF  // Katakana phonetic extensions
+        | 0x3400..=0x4DBF  // CJK unified ideographs extension A
+        | 0x4E00..=0x9FFF  // CJK unified ideographs
+        | 0xAC00..=0xD7A3  // Hangul syllables
+        | 0xF900..=0xFAFF  // CJK compatibility ideographs
+        | 0x20000..=0x2A6DF // CJK unified ideographs extension B
+    )
+}
+
+fn build_token(string: &str, start: (usize, usize), end: (usize, usize)) -> Token {
+    let char_range = start.0..end.0;
+    let byte_range = start.1..end.1;
+    let value = string[byte_range.clone()].to_string();
+    Token {
+        value,
+        char_range,
+        byte_range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_values(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|token| token.value.as_str()).collect()
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace_and_punctuation() {
+        // Given
+        let text = "Hello, world!";
+
+        // When
+        let tokens = tokenize(text, Language::EN);
+
+        // Then
+        assert_eq!(vec!["Hello", ",", "world", "!"], token_values(&tokens));
+    }
+
+    #[test]
+    fn tokenize_computes_char_and_byte_ranges() {
+        // Given
+        let text = "Hellö world";
+
+        // When
+        let tokens = tokenize(text, Language::EN);
+
+        // Then
+        assert_eq!(0..5, tokens[0].char_range);
+        assert_eq!(0..6, tokens[0].byte_range);
+        assert_eq!("Hellö", &text[tokens[0].byte_range.clone()]);
+        assert_eq!(6..11, tokens[1].char_range);
+    }
+
+    #[test]
+    fn tokenize_splits_per_char_for_japanese_and_korean() {
+        // Given
+        let text = "東京タワー";
+
+        // When
+        let tokens = tokenize(text, Language::JA);
+
+        // Then
+        assert_eq!(
+            vec!["東", "京", "タ", "ワ", "ー"],
+            token_values(&tokens)
+        );
+    }
+
+    #[test]
+    fn tokenize_keeps_digit_runs_grouped_in_japanese() {
+        // Given
+        let text = "東京2020";
+
+        // When
+        let tokens = tokenize(text, Language::JA);
+
+        // Then
+        assert_eq!(vec!["東", "京", "2020"], token_values(&tokens));
+    }
+
+    #[test]
+    fn tokenize_keeps_embedded_latin_words_grouped_in_japanese() {
+        // Given
+        let text = "iPhone が 好き";
+
+        // When
+        let tokens = tokenize(text, Language::JA);
+
+        // Then
+        assert_eq!(vec!["iPhone", "が", "好", "き"], token_values(&tokens));
+    }
+
+    #[test]
+    fn tokenize_does_not_split_per_char_for_other_languages() {
+        // Given
+        let text = "hello world";
+
+        // When
+        let tokens = tokenize(text, Language::EN);
+
+        // Then
+        assert_eq!(vec!["hello", "world"], token_values(&tokens));
+    }
+}